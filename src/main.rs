@@ -1,18 +1,21 @@
 use anyhow::{Context, Result};
 use clap::Parser;
 use git2::Repository;
+use regex::Regex;
 use std::cmp::Ordering;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::fmt;
-use std::path::PathBuf;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use time::OffsetDateTime;
 
 fn main() -> Result<()> {
     let args = Args::parse();
     let repository = Repository::discover(".").context("failed to open git repository")?;
 
-    let mut changes = std::fs::read_dir(".changes")
+    let changes = std::fs::read_dir(".changes")
         .context("failed to open directory `.changes`")?
         .map(|e| Ok(Change::from_path(&e?.path(), &repository)?))
         .collect::<Result<Vec<_>>>()
@@ -28,36 +31,161 @@ fn main() -> Result<()> {
 
             println!("{level}")
         }
-        Command::CompileChangelog { new_version: version } => {
-            changes.sort_by(highest_priority_then_chronologically);
-
-            let (year, month, day) = OffsetDateTime::now_utc().date().to_calendar_date();
-
-            println!("## {version} - {year}-{}-{day}\n", u8::from(month));
-
-            let mut changes_by_kind =
-                changes
-                    .into_iter()
-                    .fold(HashMap::<_, Vec<_>>::new(), |mut map, change| {
-                        map.entry(change.kind).or_default().push(change);
-
-                        map
-                    });
-
-            for kind in [
-                Kind::Added,
-                Kind::Fixed,
-                Kind::Changed,
-                Kind::Removed,
-                Kind::Deprecated,
-                Kind::Security,
-            ] {
-                if let Entry::Occupied(changes) = changes_by_kind.entry(kind) {
-                    println!("### {}\n", kind.header());
-
-                    for change in changes.get() {
-                        println!("- {}", change.content)
-                    }
+        Command::NextVersion {
+            current_version,
+            pre_release,
+            finalize,
+        } => {
+            let next_version =
+                resolve_next_version(&changes, &current_version, pre_release.as_deref(), finalize)?;
+
+            println!("{next_version}")
+        }
+        Command::CompileChangelog {
+            new_version: version,
+            output,
+        } => {
+            let section = render_changelog_section(changes, &version);
+
+            match output {
+                Some(path) => prepend_changelog(&path, &section)
+                    .with_context(|| format!("failed to update {}", path.display()))?,
+                None => print!("{section}"),
+            }
+        }
+        Command::SetVersion {
+            current_version,
+            pre_release,
+            finalize,
+            targets,
+        } => {
+            let next_version =
+                resolve_next_version(&changes, &current_version, pre_release.as_deref(), finalize)?;
+
+            let targets = if targets.is_empty() {
+                vec![VersionTarget::cargo_toml(PathBuf::from("Cargo.toml"))?]
+            } else {
+                targets
+            };
+
+            for target in &targets {
+                target
+                    .apply(&next_version)
+                    .with_context(|| format!("failed to update {}", target.path.display()))?;
+            }
+
+            println!("{next_version}");
+        }
+        Command::Release {
+            current_version,
+            pre_release,
+            finalize,
+            targets,
+            dry_run,
+            no_commit,
+            no_tag,
+        } => {
+            let next_version =
+                resolve_next_version(&changes, &current_version, pre_release.as_deref(), finalize)?;
+
+            let targets = if targets.is_empty() {
+                vec![VersionTarget::cargo_toml(PathBuf::from("Cargo.toml"))?]
+            } else {
+                targets
+            };
+
+            let consumed_paths = changes.iter().map(|c| c.path.clone()).collect::<Vec<_>>();
+            let section = render_changelog_section(changes, &next_version);
+
+            if dry_run {
+                println!("would bump {current_version} -> {next_version}");
+                println!("would prepend the following section into CHANGELOG.md:\n");
+                println!("{section}");
+                for path in &consumed_paths {
+                    println!("would delete {}", path.display());
+                }
+                for target in &targets {
+                    println!("would update {}", target.path.display());
+                }
+                if !no_commit {
+                    println!("would commit the changes");
+                }
+                if would_tag(no_commit, no_tag) {
+                    println!("would tag v{next_version}");
+                }
+
+                return Ok(());
+            }
+
+            prepend_changelog(Path::new("CHANGELOG.md"), &section)
+                .context("failed to update CHANGELOG.md")?;
+
+            for path in &consumed_paths {
+                std::fs::remove_file(path)
+                    .with_context(|| format!("failed to remove {}", path.display()))?;
+            }
+
+            for target in &targets {
+                target
+                    .apply(&next_version)
+                    .with_context(|| format!("failed to update {}", target.path.display()))?;
+            }
+
+            if !no_commit {
+                let mut index = repository.index().context("failed to open git index")?;
+                index
+                    .add_path(Path::new("CHANGELOG.md"))
+                    .context("failed to stage CHANGELOG.md")?;
+                for path in &consumed_paths {
+                    index.remove_path(path).with_context(|| {
+                        format!("failed to stage removal of {}", path.display())
+                    })?;
+                }
+                for target in &targets {
+                    index
+                        .add_path(&target.path)
+                        .with_context(|| format!("failed to stage {}", target.path.display()))?;
+                }
+                index.write().context("failed to write git index")?;
+
+                let tree_id = index.write_tree().context("failed to write git tree")?;
+                let tree = repository
+                    .find_tree(tree_id)
+                    .context("failed to find written tree")?;
+                let signature = repository
+                    .signature()
+                    .context("failed to determine git signature")?;
+                let parent = repository
+                    .head()
+                    .context("failed to resolve HEAD")?
+                    .peel_to_commit()
+                    .context("failed to peel HEAD to a commit")?;
+
+                let commit_id = repository
+                    .commit(
+                        Some("HEAD"),
+                        &signature,
+                        &signature,
+                        &format!("Release {next_version}"),
+                        &tree,
+                        &[&parent],
+                    )
+                    .context("failed to create release commit")?;
+
+                if would_tag(no_commit, no_tag) {
+                    let commit = repository
+                        .find_object(commit_id, None)
+                        .context("failed to find release commit")?;
+
+                    repository
+                        .tag(
+                            &format!("v{next_version}"),
+                            &commit,
+                            &signature,
+                            &format!("Release {next_version}"),
+                            false,
+                        )
+                        .context("failed to create release tag")?;
                 }
             }
         }
@@ -66,7 +194,98 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+fn render_changelog_section(mut changes: Vec<Change>, version: &semver::Version) -> String {
+    changes.sort_by(highest_priority_then_chronologically);
+
+    let (year, month, day) = OffsetDateTime::now_utc().date().to_calendar_date();
+
+    let mut section = String::new();
+    let _ = writeln!(section, "## {version} - {year}-{}-{day}\n", u8::from(month));
+
+    let mut changes_by_kind =
+        changes
+            .into_iter()
+            .fold(HashMap::<_, Vec<_>>::new(), |mut map, change| {
+                map.entry(change.kind).or_default().push(change);
+
+                map
+            });
+
+    for kind in [
+        Kind::Added,
+        Kind::Fixed,
+        Kind::Changed,
+        Kind::Removed,
+        Kind::Deprecated,
+        Kind::Security,
+    ] {
+        if let Entry::Occupied(changes) = changes_by_kind.entry(kind) {
+            let _ = writeln!(section, "### {}\n", kind.header());
+
+            for change in changes.get() {
+                let _ = writeln!(section, "- {}", change.content);
+            }
+
+            section.push('\n');
+        }
+    }
+
+    section
+}
+
+/// Prepends `section` into `path`, creating it with a standard Keep a
+/// Changelog preamble if it does not exist yet.
+///
+/// The section is inserted right after the top-level title and any
+/// `## [Unreleased]` block, preserving everything below it.
+fn prepend_changelog(path: &Path, section: &str) -> Result<()> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(existing) => {
+            let at = insertion_point(&existing);
+            format!("{}{section}{}", &existing[..at], &existing[at..])
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let preamble = "# Changelog\n\nAll notable changes to this project will be documented in this file.\n\n";
+            format!("{preamble}{section}")
+        }
+        Err(e) => return Err(e).context(format!("failed to read {}", path.display())),
+    };
+
+    std::fs::write(path, content).with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Finds the byte offset at which a new release section should be
+/// inserted: after the top-level title, skipping an existing
+/// `## [Unreleased]` section if present.
+fn insertion_point(content: &str) -> usize {
+    let mut offset = 0;
+    let mut lines = content.split_inclusive('\n').peekable();
+
+    if matches!(lines.peek(), Some(line) if line.trim_start().starts_with("# ")) {
+        offset += lines.next().unwrap().len();
+    }
+
+    while matches!(lines.peek(), Some(line) if line.trim().is_empty()) {
+        offset += lines.next().unwrap().len();
+    }
+
+    if matches!(lines.peek(), Some(line) if line.trim_start().to_lowercase().starts_with("## [unreleased]"))
+    {
+        offset += lines.next().unwrap().len();
+
+        for line in lines {
+            if line.trim_start().starts_with("## ") {
+                break;
+            }
+            offset += line.len();
+        }
+    }
+
+    offset
+}
+
 struct Change {
+    path: PathBuf,
     kind: Kind,
     breaking: Option<bool>,
     priority: Option<u8>,
@@ -91,6 +310,7 @@ impl Change {
             .time();
 
         Ok(Change {
+            path: path.clone(),
             kind: frontmatter.kind,
             breaking: frontmatter.breaking,
             priority: frontmatter.priority,
@@ -162,6 +382,91 @@ impl Change {
     }
 }
 
+/// A manifest file whose version field should be rewritten as part of a
+/// bump, identified by a search pattern and a replacement template
+/// containing a `{{version}}` placeholder.
+#[derive(Clone)]
+struct VersionTarget {
+    path: PathBuf,
+    pattern: Regex,
+    replacement: String,
+}
+
+impl VersionTarget {
+    /// The default target: the `[package] version` line of a `Cargo.toml`.
+    fn cargo_toml(path: PathBuf) -> Result<Self> {
+        Ok(VersionTarget {
+            path,
+            pattern: Regex::new(r#"(?m)^version = "[^"]*""#).expect("hard-coded regex is valid"),
+            replacement: "version = \"{{version}}\"".to_string(),
+        })
+    }
+
+    fn apply(&self, version: &semver::Version) -> Result<()> {
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("failed to read {}", self.path.display()))?;
+
+        write_atomically(&self.path, &self.rewrite(&content, version)?)
+    }
+
+    /// Replaces the single line matching `self.pattern` with the rendered
+    /// `self.replacement`. Errors rather than silently doing nothing (or
+    /// rewriting the wrong line) when the pattern doesn't match exactly once.
+    fn rewrite(&self, content: &str, version: &semver::Version) -> Result<String> {
+        match self.pattern.find_iter(content).count() {
+            0 => anyhow::bail!(
+                "pattern `{}` did not match anything in {}",
+                self.pattern.as_str(),
+                self.path.display()
+            ),
+            1 => {}
+            matches => anyhow::bail!(
+                "pattern `{}` matched {matches} places in {}, expected exactly one",
+                self.pattern.as_str(),
+                self.path.display()
+            ),
+        }
+
+        let replacement = self
+            .replacement
+            .replace("{{version}}", &version.to_string());
+
+        Ok(self
+            .pattern
+            .replace(content, replacement.as_str())
+            .into_owned())
+    }
+}
+
+impl FromStr for VersionTarget {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.splitn(3, '=').collect::<Vec<_>>().as_slice() {
+            [path] => VersionTarget::cargo_toml(PathBuf::from(path)),
+            [path, pattern, replacement] => Ok(VersionTarget {
+                path: PathBuf::from(path),
+                pattern: Regex::new(pattern).context("invalid target pattern")?,
+                replacement: replacement.to_string(),
+            }),
+            _ => anyhow::bail!("expected `path` or `path=pattern=replacement`"),
+        }
+    }
+}
+
+/// Writes `content` to `path` via a temporary file and a rename, so
+/// readers never observe a partially written manifest.
+fn write_atomically(path: &Path, content: &str) -> Result<()> {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+
+    std::fs::write(&tmp_path, content)
+        .with_context(|| format!("failed to write {}", tmp_path.display()))?;
+    std::fs::rename(&tmp_path, path)
+        .with_context(|| format!("failed to move {} into place", tmp_path.display()))
+}
+
 fn highest_priority_then_chronologically(a: &Change, b: &Change) -> Ordering {
     b.priority.cmp(&a.priority).then(a.created.cmp(&b.created))
 }
@@ -174,8 +479,73 @@ struct Args {
 
 #[derive(clap::Subcommand)]
 enum Command {
-    ComputeBumpLevel { current_version: semver::Version },
-    CompileChangelog { new_version: semver::Version },
+    ComputeBumpLevel {
+        current_version: semver::Version,
+    },
+    NextVersion {
+        current_version: semver::Version,
+        /// Cut a pre-release with this channel id (e.g. `rc`, `beta`)
+        /// instead of a stable version.
+        #[arg(long = "pre-release")]
+        pre_release: Option<String>,
+        /// Strip any pre-release/build metadata to promote to a stable version.
+        #[arg(long)]
+        finalize: bool,
+    },
+    CompileChangelog {
+        new_version: semver::Version,
+        /// Prepend the compiled section into this Keep a Changelog file
+        /// instead of printing it to stdout.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    SetVersion {
+        current_version: semver::Version,
+        /// Cut a pre-release with this channel id (e.g. `rc`, `beta`)
+        /// instead of a stable version.
+        #[arg(long = "pre-release")]
+        pre_release: Option<String>,
+        /// Strip any pre-release/build metadata to promote to a stable version.
+        #[arg(long)]
+        finalize: bool,
+        /// A manifest to rewrite, as `path` (to replace the `[package]
+        /// version` line) or `path=pattern=replacement` (where
+        /// `replacement` may contain a `{{version}}` placeholder).
+        /// Defaults to `./Cargo.toml` when omitted.
+        #[arg(long = "target")]
+        targets: Vec<VersionTarget>,
+    },
+    Release {
+        current_version: semver::Version,
+        /// Cut a pre-release with this channel id (e.g. `rc`, `beta`)
+        /// instead of a stable version.
+        #[arg(long = "pre-release")]
+        pre_release: Option<String>,
+        /// Strip any pre-release/build metadata to promote to a stable version.
+        #[arg(long)]
+        finalize: bool,
+        /// A manifest to rewrite as part of the release, same syntax as
+        /// `set-version`'s `--target`. Defaults to `./Cargo.toml` when omitted.
+        #[arg(long = "target")]
+        targets: Vec<VersionTarget>,
+        /// Print the planned actions without touching the repository.
+        #[arg(long)]
+        dry_run: bool,
+        /// Don't create a release commit. Implies `--no-tag`, since the
+        /// tag has nothing to point at without a release commit.
+        #[arg(long)]
+        no_commit: bool,
+        /// Don't create a `v{version}` tag.
+        #[arg(long)]
+        no_tag: bool,
+    },
+}
+
+/// Whether `release` should create a `v{version}` tag, given its flags.
+/// Tagging always points at the release commit, so it never happens
+/// without one.
+fn would_tag(no_commit: bool, no_tag: bool) -> bool {
+    !no_commit && !no_tag
 }
 
 fn parse_file_content(content: String) -> Result<(FrontMatter, String)> {
@@ -228,6 +598,89 @@ enum BumpLevel {
     Patch = 0,
 }
 
+impl BumpLevel {
+    /// Applies this bump level to `version`, producing the next version.
+    ///
+    /// The pre-1.0 downgrade (major changes only bump `minor`, minor
+    /// changes only bump `patch`) already happened in
+    /// `Change::compute_bump_level`, so `self` is applied directly here.
+    fn apply(self, version: &semver::Version) -> semver::Version {
+        match self {
+            BumpLevel::Major => semver::Version::new(version.major + 1, 0, 0),
+            BumpLevel::Minor => semver::Version::new(version.major, version.minor + 1, 0),
+            BumpLevel::Patch => {
+                semver::Version::new(version.major, version.minor, version.patch + 1)
+            }
+        }
+    }
+}
+
+/// Computes the next version for `current`, honoring an optional
+/// pre-release channel.
+///
+/// If `current` already carries a pre-release tag matching `pre_release`,
+/// only its numeric counter is incremented. Otherwise, `level` is applied
+/// to the core version and, if `pre_release` is set, a `{id}.1` tag is
+/// appended.
+fn compute_next_version(
+    level: BumpLevel,
+    current: &semver::Version,
+    pre_release: Option<&str>,
+) -> Result<semver::Version> {
+    let Some(id) = pre_release else {
+        return Ok(level.apply(current));
+    };
+
+    if let Some(counter) = matching_pre_release_counter(current, id) {
+        let mut version = current.clone();
+        version.pre = semver::Prerelease::new(&format!("{id}.{}", counter + 1))
+            .context("failed to construct pre-release tag")?;
+        return Ok(version);
+    }
+
+    let mut version = level.apply(current);
+    version.pre = semver::Prerelease::new(&format!("{id}.1"))
+        .context("failed to construct pre-release tag")?;
+    Ok(version)
+}
+
+/// If `version`'s pre-release tag is `{id}.N`, returns `N`.
+fn matching_pre_release_counter(version: &semver::Version, id: &str) -> Option<u64> {
+    let (pre_id, counter) = version.pre.split_once('.')?;
+
+    (pre_id == id).then(|| counter.parse().ok()).flatten()
+}
+
+/// Strips any pre-release/build metadata, promoting `version` to stable.
+fn finalize_version(version: &semver::Version) -> semver::Version {
+    let mut version = version.clone();
+    version.pre = semver::Prerelease::EMPTY;
+    version.build = semver::BuildMetadata::EMPTY;
+    version
+}
+
+/// Resolves the next version for `current_version` from `changes`,
+/// honoring `--finalize`/`--pre-release`. Shared by `next-version`,
+/// `set-version`, and `release` so all three agree on the same version.
+fn resolve_next_version(
+    changes: &[Change],
+    current_version: &semver::Version,
+    pre_release: Option<&str>,
+    finalize: bool,
+) -> Result<semver::Version> {
+    if finalize {
+        return Ok(finalize_version(current_version));
+    }
+
+    let level = changes
+        .iter()
+        .map(|change| change.compute_bump_level(current_version))
+        .max()
+        .context("expected at least one changelog entry")?;
+
+    compute_next_version(level, current_version, pre_release)
+}
+
 impl fmt::Display for BumpLevel {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -253,6 +706,7 @@ mod tests {
     fn sort_order() {
         let mut changes = [
             Change {
+                path: PathBuf::new(),
                 kind: Kind::Added,
                 breaking: None,
                 priority: Some(1),
@@ -260,6 +714,7 @@ mod tests {
                 content: "A".to_string(),
             },
             Change {
+                path: PathBuf::new(),
                 kind: Kind::Added,
                 breaking: None,
                 priority: None,
@@ -267,6 +722,7 @@ mod tests {
                 content: "B".to_string(),
             },
             Change {
+                path: PathBuf::new(),
                 kind: Kind::Added,
                 breaking: None,
                 priority: None,
@@ -274,6 +730,7 @@ mod tests {
                 content: "C".to_string(),
             },
             Change {
+                path: PathBuf::new(),
                 kind: Kind::Added,
                 breaking: None,
                 priority: Some(5),
@@ -281,6 +738,7 @@ mod tests {
                 content: "D".to_string(),
             },
             Change {
+                path: PathBuf::new(),
                 kind: Kind::Added,
                 breaking: None,
                 priority: Some(5),
@@ -334,8 +792,195 @@ mod tests {
         );
     }
 
+    #[test]
+    fn applies_bump_level_correctly() {
+        assert_eq!(
+            BumpLevel::Major.apply(&"1.2.3".parse().unwrap()),
+            "2.0.0".parse().unwrap()
+        );
+        assert_eq!(
+            BumpLevel::Minor.apply(&"1.2.3".parse().unwrap()),
+            "1.3.0".parse().unwrap()
+        );
+        assert_eq!(
+            BumpLevel::Patch.apply(&"1.2.3".parse().unwrap()),
+            "1.2.4".parse().unwrap()
+        );
+
+        // `apply` bumps whichever field the level names, regardless of
+        // major version; the pre-1.0 downgrade already happened upstream
+        // in `compute_bump_level` (see `applies_chained_bump_level_for_0x`).
+        assert_eq!(
+            BumpLevel::Major.apply(&"0.2.3".parse().unwrap()),
+            "1.0.0".parse().unwrap()
+        );
+        assert_eq!(
+            BumpLevel::Minor.apply(&"0.2.3".parse().unwrap()),
+            "0.3.0".parse().unwrap()
+        );
+        assert_eq!(
+            BumpLevel::Patch.apply(&"0.2.3".parse().unwrap()),
+            "0.2.4".parse().unwrap()
+        );
+
+        // Bumping clears any pre-release/build metadata.
+        assert_eq!(
+            BumpLevel::Patch.apply(&"1.2.3-rc.1".parse().unwrap()),
+            "1.2.4".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn applies_chained_bump_level_for_0x() {
+        // A breaking `Changed` entry on a 0.x version already comes out of
+        // `compute_bump_level` shifted down to `Minor` (0.x has no "major"
+        // rank); `apply` must not shift it again.
+        let version = "0.1.0".parse().unwrap();
+        let level = entry(Kind::Changed, true).compute_bump_level(&version);
+
+        assert_eq!(level, BumpLevel::Minor);
+        assert_eq!(level.apply(&version), "0.2.0".parse().unwrap());
+    }
+
+    #[test]
+    fn computes_next_version_with_pre_release_channel() {
+        // No existing pre-release tag: apply the core bump, then start at `.1`.
+        assert_eq!(
+            compute_next_version(BumpLevel::Minor, &"1.2.3".parse().unwrap(), Some("rc")).unwrap(),
+            "1.3.0-rc.1".parse().unwrap()
+        );
+
+        // Matching pre-release tag: only the counter advances.
+        assert_eq!(
+            compute_next_version(BumpLevel::Minor, &"1.3.0-rc.1".parse().unwrap(), Some("rc"))
+                .unwrap(),
+            "1.3.0-rc.2".parse().unwrap()
+        );
+
+        // A different channel id starts its own counter from the core bump.
+        assert_eq!(
+            compute_next_version(
+                BumpLevel::Minor,
+                &"1.3.0-rc.2".parse().unwrap(),
+                Some("beta")
+            )
+            .unwrap(),
+            "1.4.0-beta.1".parse().unwrap()
+        );
+
+        // No channel requested: falls back to a plain core bump.
+        assert_eq!(
+            compute_next_version(BumpLevel::Minor, &"1.2.3".parse().unwrap(), None).unwrap(),
+            "1.3.0".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn finalizes_pre_release_version() {
+        assert_eq!(
+            finalize_version(&"1.2.3-rc.3".parse().unwrap()),
+            "1.2.3".parse().unwrap()
+        );
+        assert_eq!(
+            finalize_version(&"1.2.3".parse().unwrap()),
+            "1.2.3".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn no_commit_implies_no_tag() {
+        assert!(would_tag(false, false));
+        assert!(!would_tag(false, true));
+        assert!(!would_tag(true, false));
+        assert!(!would_tag(true, true));
+    }
+
+    #[test]
+    fn insertion_point_skips_title_and_unreleased_section() {
+        let content = "\
+# Changelog
+
+## [Unreleased]
+
+- some draft note
+
+## 1.0.0 - 2024-01-01
+
+- initial release
+";
+
+        let at = insertion_point(content);
+
+        assert_eq!(
+            &content[at..],
+            "## 1.0.0 - 2024-01-01\n\n- initial release\n"
+        );
+    }
+
+    #[test]
+    fn insertion_point_handles_missing_unreleased_section() {
+        let content = "\
+# Changelog
+
+## 1.0.0 - 2024-01-01
+
+- initial release
+";
+
+        let at = insertion_point(content);
+
+        assert_eq!(
+            &content[at..],
+            "## 1.0.0 - 2024-01-01\n\n- initial release\n"
+        );
+    }
+
+    #[test]
+    fn rewrites_cargo_toml_version_line() {
+        let target = VersionTarget::cargo_toml(PathBuf::from("Cargo.toml")).unwrap();
+        let content = "[package]\nname = \"semverlog\"\nversion = \"0.1.0\"\nedition = \"2021\"\n";
+
+        let rewritten = target.rewrite(content, &"0.2.0".parse().unwrap()).unwrap();
+
+        assert_eq!(
+            rewritten,
+            "[package]\nname = \"semverlog\"\nversion = \"0.2.0\"\nedition = \"2021\"\n"
+        );
+    }
+
+    #[test]
+    fn rewrite_errors_when_pattern_does_not_match() {
+        let target = VersionTarget::cargo_toml(PathBuf::from("Cargo.toml")).unwrap();
+        let content = "[package]\nname = \"semverlog\"\nversion.workspace = true\n";
+
+        assert!(target.rewrite(content, &"0.2.0".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn rewrite_errors_when_pattern_matches_more_than_once() {
+        let target = VersionTarget::cargo_toml(PathBuf::from("Cargo.toml")).unwrap();
+        let content = "version = \"0.1.0\"\nversion = \"0.1.0\"\n";
+
+        assert!(target.rewrite(content, &"0.2.0".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn parses_custom_version_target() {
+        let target: VersionTarget =
+            "package.json=\"version\": \"[^\"]*\"=\"version\": \"{{version}}\""
+                .parse()
+                .unwrap();
+
+        let rewritten = target
+            .rewrite("{\n  \"version\": \"1.2.3\"\n}", &"1.3.0".parse().unwrap())
+            .unwrap();
+
+        assert_eq!(rewritten, "{\n  \"version\": \"1.3.0\"\n}");
+    }
+
     fn entry(kind: Kind, breaking: impl Into<Option<bool>>) -> Change {
         Change {
+            path: PathBuf::new(),
             kind,
             breaking: breaking.into(),
             priority: None,